@@ -1,9 +1,10 @@
-use crc64fast_nvme::Digest;
-/// Generates CRC-64/NVME checksums, using SIMD-accelerated
-/// carryless-multiplication, from a file on disk.
+use crc64fast_nvme::{algorithm, Algorithm, Digest};
+/// Generates CRC-64 checksums, using the table-driven implementation in
+/// `crc64fast_nvme`, from a file on disk. Defaults to CRC-64/NVME; see
+/// `--algo`.
 use std::env;
 use std::fs::{self, File};
-use std::io::{self, BufReader, Read};
+use std::io::{self, Read};
 use std::process::ExitCode;
 
 const CRC_NVME: crc::Algorithm<u64> = crc::Algorithm {
@@ -17,16 +18,50 @@ const CRC_NVME: crc::Algorithm<u64> = crc::Algorithm {
     residue: 0x0000000000000000,
 };
 
+const CRC_XZ: crc::Algorithm<u64> = crc::Algorithm {
+    width: 64,
+    poly: 0x42F0E1EBA9EA3693,
+    init: 0xFFFFFFFFFFFFFFFF,
+    refin: true,
+    refout: true,
+    xorout: 0xFFFFFFFFFFFFFFFF,
+    check: 0x995dc9bbdf1939fa,
+    residue: 0x49958c9abd7d353f,
+};
+
+const CRC_ISO: crc::Algorithm<u64> = crc::Algorithm {
+    width: 64,
+    poly: 0x000000000000001B,
+    init: 0xFFFFFFFFFFFFFFFF,
+    refin: true,
+    refout: true,
+    xorout: 0xFFFFFFFFFFFFFFFF,
+    check: 0xb90956c775a41001,
+    residue: 0x0000000000000000,
+};
+
 // Define a chunk size for reading files, e.g., 100MB.
 const CHUNK_SIZE: usize = 100 * 1024 * 1024;
 
-/// Calculates the CRC-64/NVME checksum for a file by reading it in chunks.
-/// This version uses the SIMD-accelerated implementation.
-fn calculate_crc_64_simd_from_file(file_path: &str) -> io::Result<u64> {
-    let mut c = Digest::new();
+/// Resolves an `--algo` name to the matching library and `crc` presets.
+fn resolve_algorithm(name: &str) -> Option<(&'static Algorithm, &'static crc::Algorithm<u64>)> {
+    match name.to_ascii_lowercase().as_str() {
+        "nvme" => Some((&algorithm::NVME, &CRC_NVME)),
+        "xz" | "ecma-182" | "ecma182" => Some((&algorithm::XZ, &CRC_XZ)),
+        "iso" => Some((&algorithm::ISO, &CRC_ISO)),
+        _ => None,
+    }
+}
 
-    let file = File::open(file_path)?;
-    let mut reader = BufReader::new(file);
+/// Calculates the checksum of everything read from `reader` under
+/// `algorithm`, using the table-driven `Digest` implementation. Shared by
+/// files, stdin, and in-memory strings, which all read through this one
+/// streaming path.
+fn calculate_crc_64_simd_from_reader<R: Read>(
+    mut reader: R,
+    algorithm: &'static Algorithm,
+) -> io::Result<u64> {
+    let mut c = Digest::new_with_algorithm(algorithm);
     let mut buffer = vec![0; CHUNK_SIZE];
 
     loop {
@@ -35,19 +70,33 @@ fn calculate_crc_64_simd_from_file(file_path: &str) -> io::Result<u64> {
             break;
         }
         c.write(&buffer[..bytes_read]);
-}
+    }
 
     Ok(c.sum64())
 }
 
-/// Calculates the CRC-64/NVME checksum for a file by reading it in chunks.
-/// This version is for validation and is typically slower.
-fn calculate_crc_64_validate_from_file(file_path: &str) -> io::Result<u64> {
-    let crc = crc::Crc::<u64>::new(&CRC_NVME);
+/// Calculates the checksum for `path` under `algorithm`, or for stdin when
+/// `path` is `-`.
+fn calculate_crc_64_simd_from_path(path: &str, algorithm: &'static Algorithm) -> io::Result<u64> {
+    if path == "-" {
+        calculate_crc_64_simd_from_reader(io::stdin().lock(), algorithm)
+    } else {
+        calculate_crc_64_simd_from_reader(File::open(path)?, algorithm)
+    }
+}
+
+/// Calculates the checksum for a file by reading it in chunks, using the
+/// `crc` crate's table implementation. This version is for validation and is
+/// typically slower.
+fn calculate_crc_64_validate_from_file(
+    file_path: &str,
+    algorithm: &'static crc::Algorithm<u64>,
+) -> io::Result<u64> {
+    let crc = crc::Crc::<u64>::new(algorithm);
     let mut digest = crc.digest();
 
     let file = File::open(file_path)?;
-    let mut reader = BufReader::new(file);
+    let mut reader = io::BufReader::new(file);
     let mut buffer = vec![0; CHUNK_SIZE];
 
     loop {
@@ -61,16 +110,16 @@ fn calculate_crc_64_validate_from_file(file_path: &str) -> io::Result<u64> {
     Ok(digest.finalize())
 }
 
-fn calculate_crc_64_simd_from_string(input: &str) -> u64 {
-    let mut c = Digest::new();
-
-    c.write(input.as_bytes());
-
-    c.sum64()
+fn calculate_crc_64_simd_from_string(input: &str, algorithm: &'static Algorithm) -> u64 {
+    calculate_crc_64_simd_from_reader(input.as_bytes(), algorithm)
+        .expect("reading from an in-memory byte slice cannot fail")
 }
 
-fn calculate_crc_64_validate_from_string(input: &str) -> u64 {
-    let crc = crc::Crc::<u64>::new(&CRC_NVME);
+fn calculate_crc_64_validate_from_string(
+    input: &str,
+    algorithm: &'static crc::Algorithm<u64>,
+) -> u64 {
+    let crc = crc::Crc::<u64>::new(algorithm);
 
     let mut digest = crc.digest();
 
@@ -79,63 +128,374 @@ fn calculate_crc_64_validate_from_string(input: &str) -> u64 {
     digest.finalize()
 }
 
+/// Looks for `--parallel` or `--parallel=N` among `flags`, returning the
+/// requested segment count (defaulting to the available parallelism when no
+/// `=N` is given).
+fn parse_parallel_flag(flags: &[String]) -> Option<usize> {
+    flags.iter().find_map(|flag| {
+        if flag == "--parallel" {
+            let default_segments = std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1);
+            Some(default_segments)
+        } else {
+            flag.strip_prefix("--parallel=")
+                .and_then(|n| n.parse::<usize>().ok())
+        }
+    })
+}
+
+/// Looks for `--algo=NAME` among `flags`, resolving it to the matching
+/// library and `crc` presets. Defaults to NVME, and falls back to NVME (with
+/// a warning) for an unrecognized name.
+fn parse_algo_flag(flags: &[String]) -> (&'static Algorithm, &'static crc::Algorithm<u64>) {
+    let Some(name) = flags.iter().find_map(|f| f.strip_prefix("--algo=")) else {
+        return (&algorithm::NVME, &CRC_NVME);
+    };
+
+    match resolve_algorithm(name) {
+        Some(resolved) => resolved,
+        None => {
+            println!("Unknown --algo '{name}', falling back to nvme. Options: nvme, xz, iso.");
+            (&algorithm::NVME, &CRC_NVME)
+        }
+    }
+}
+
+/// Formats a checksum the way `--tag`/`--hex` ask for: BSD-tagged
+/// (`<algorithm.name> (filename) = <hex>`), bare lowercase hex, or the
+/// default bare decimal.
+fn format_checksum(
+    checksum: u64,
+    filename: &str,
+    algorithm: &Algorithm,
+    tag: bool,
+    hex: bool,
+) -> String {
+    if tag {
+        format!("{} ({filename}) = {checksum:016x}", algorithm.name)
+    } else if hex {
+        format!("{checksum:016x}")
+    } else {
+        format!("{checksum}")
+    }
+}
+
+/// Parses one line of a sumfile, in either the BSD-tagged form
+/// (`<name> (filename) = <hex>`) or the coreutils form (`<hex>  filename`).
+/// Returns `None` for blank or unrecognized lines. The tag `name` is `None`
+/// for the untagged coreutils form, which doesn't encode an algorithm.
+fn parse_check_line(line: &str) -> Option<(Option<&str>, &str, u64)> {
+    let line = line.trim_end();
+    if line.is_empty() {
+        return None;
+    }
+
+    if let Some((name, rest)) = line.split_once(" (") {
+        if let Some((filename, hex)) = rest.split_once(") = ") {
+            let checksum = u64::from_str_radix(hex.trim(), 16).ok()?;
+            return Some((Some(name), filename, checksum));
+        }
+    }
+
+    let (hex, filename) = line.split_once("  ")?;
+    let checksum = u64::from_str_radix(hex.trim(), 16).ok()?;
+    Some((None, filename, checksum))
+}
+
+/// Recomputes the checksum of every file listed in `sumfile` under
+/// `algorithm` and reports `OK`/`FAILED` for each. A tagged line whose
+/// algorithm name doesn't match `algorithm` is reported `FAILED` without
+/// recomputing, since it was checksummed under a different variant. Returns
+/// whether every listed file matched.
+fn run_check(sumfile: &str, algorithm: &'static Algorithm) -> io::Result<bool> {
+    let contents = fs::read_to_string(sumfile)?;
+    let mut all_ok = true;
+
+    for line in contents.lines() {
+        let Some((tag_name, filename, expected)) = parse_check_line(line) else {
+            continue;
+        };
+
+        if let Some(tag_name) = tag_name {
+            if tag_name != algorithm.name {
+                println!(
+                    "{filename}: FAILED (computed as {tag_name}, expected {})",
+                    algorithm.name
+                );
+                all_ok = false;
+                continue;
+            }
+        }
+
+        match calculate_crc_64_simd_from_path(filename, algorithm) {
+            Ok(actual) if actual == expected => println!("{filename}: OK"),
+            Ok(_) => {
+                println!("{filename}: FAILED");
+                all_ok = false;
+            }
+            Err(e) => {
+                println!("{filename}: FAILED open or read ({e})");
+                all_ok = false;
+            }
+        }
+    }
+
+    Ok(all_ok)
+}
+
+/// Splits the arguments following the input type into positional inputs
+/// (file paths, a sumfile, or a string) and `--flag`-style options.
+fn split_args_and_flags(args: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut positional = Vec::new();
+    let mut flags = Vec::new();
+
+    for arg in args {
+        if arg.starts_with("--") {
+            flags.push(arg.clone());
+        } else {
+            positional.push(arg.clone());
+        }
+    }
+
+    (positional, flags)
+}
+
+fn checksum_one_file(
+    path: &str,
+    flags: &[String],
+    algorithm: &'static Algorithm,
+    crc_algorithm: &'static crc::Algorithm<u64>,
+) -> io::Result<u64> {
+    let use_slow_validation = flags.iter().any(|f| f == "--validate-slow");
+    let parallel_segments = parse_parallel_flag(flags);
+
+    if use_slow_validation {
+        if path == "-" {
+            let mut input = Vec::new();
+            io::stdin().lock().read_to_end(&mut input)?;
+            Ok(crc::Crc::<u64>::new(crc_algorithm).checksum(&input))
+        } else {
+            calculate_crc_64_validate_from_file(path, crc_algorithm)
+        }
+    } else if let Some(segments) = parallel_segments {
+        if path == "-" {
+            calculate_crc_64_simd_from_reader(io::stdin().lock(), algorithm)
+        } else {
+            Digest::from_mmap_parallel(path, segments, algorithm).map(|d| d.sum64())
+        }
+    } else {
+        calculate_crc_64_simd_from_path(path, algorithm)
+    }
+}
+
 fn main() -> ExitCode {
     let args: Vec<String> = env::args().collect();
 
     if args.len() < 3 {
-        println!("Usage: crc_64_nvm_checksum [--inputType] [inputString] [--validate-slow]");
-        println!("Example for a file: crc_64_nvm_checksum --file /path/to/file");
+        println!("Usage: crc_64_nvm_checksum [--inputType] [inputs...] [--validate-slow] [--parallel[=N]] [--tag] [--hex] [--algo=NAME]");
+        println!("Example for files: crc_64_nvm_checksum --file a.bin b.bin -");
         println!("Example for a string: crc_64_nvm_checksum --string 123456789");
-        println!("Optionally including '--validate-slow' in the argument list will skip SIMD calculation, typically just for testing.");
+        println!("Example to verify a sumfile: crc_64_nvm_checksum --check checksums.txt");
+        println!("A path of '-' with '--file' reads that input from stdin.");
+        println!("Optionally including '--validate-slow' in the argument list will use the `crc` crate's table implementation instead, typically just for testing.");
+        println!("Optionally including '--parallel' (or '--parallel=N') with '--file' memory-maps each file and checksums it across N threads.");
+        println!("Optionally including '--tag' with '--file' prints 'CRC64NVME (filename) = <hex>' instead of a bare decimal.");
+        println!("Optionally including '--hex' with '--file' or '--string' prints lowercase hex instead of a bare decimal.");
+        println!("Optionally including '--algo=NAME' selects the CRC-64 variant: nvme (default), xz, or iso.");
 
         return ExitCode::from(1);
     }
 
     let input_type = &args[1];
-    let input = &args[2];
+    let (positional, flags) = split_args_and_flags(&args[2..]);
+    let (algorithm, crc_algorithm) = parse_algo_flag(&flags);
 
     match input_type.as_str() {
         "--file" => {
-            if fs::metadata(input).is_err() {
-                println!("Couldn't open file {}", input);
-            return ExitCode::from(1);
-        }
+            if positional.is_empty() {
+                println!("No input files provided. Use - to read from stdin.");
+                return ExitCode::from(1);
+            }
 
-            let use_slow_validation = args.len() == 4 && args[3] == "--validate-slow";
+            let tag = flags.iter().any(|f| f == "--tag");
+            let hex = flags.iter().any(|f| f == "--hex");
+            let mut exit_code = ExitCode::SUCCESS;
 
-            let result = if use_slow_validation {
-                calculate_crc_64_validate_from_file(input)
-            } else {
-                calculate_crc_64_simd_from_file(input)
-            };
+            for path in &positional {
+                if path != "-" && fs::metadata(path).is_err() {
+                    println!("Couldn't open file {}", path);
+                    exit_code = ExitCode::from(1);
+                    continue;
+                }
 
-            match result {
-                Ok(checksum) => {
-                    println!("{}", checksum);
-                    ExitCode::SUCCESS
-    }
-                Err(e) => {
-                    println!("Error processing file {}: {}", input, e);
-                    ExitCode::from(1)
-        }
+                match checksum_one_file(path, &flags, algorithm, crc_algorithm) {
+                    Ok(checksum) => {
+                        println!("{}", format_checksum(checksum, path, algorithm, tag, hex))
+                    }
+                    Err(e) => {
+                        println!("Error processing file {}: {}", path, e);
+                        exit_code = ExitCode::from(1);
+                    }
+                }
+            }
+
+            exit_code
         }
-    }
         "--string" => {
-            let use_slow_validation = args.len() == 4 && args[3] == "--validate-slow";
+            let Some(input) = positional.first() else {
+                println!("No input string provided.");
+                return ExitCode::from(1);
+            };
+
+            let use_slow_validation = flags.iter().any(|f| f == "--validate-slow");
+            let hex = flags.iter().any(|f| f == "--hex");
 
             let checksum = if use_slow_validation {
-                calculate_crc_64_validate_from_string(input)
+                calculate_crc_64_validate_from_string(input, crc_algorithm)
             } else {
-                calculate_crc_64_simd_from_string(input)
+                calculate_crc_64_simd_from_string(input, algorithm)
             };
-            println!("{}", checksum);
+
+            if hex {
+                println!("{checksum:016x}");
+            } else {
+                println!("{checksum}");
+            }
             ExitCode::SUCCESS
         }
 
+        "--check" => {
+            let Some(sumfile) = positional.first() else {
+                println!("No sumfile provided.");
+                return ExitCode::from(1);
+            };
+
+            match run_check(sumfile, algorithm) {
+                Ok(true) => ExitCode::SUCCESS,
+                Ok(false) => ExitCode::from(1),
+                Err(e) => {
+                    println!("Error reading sumfile {}: {}", sumfile, e);
+                    ExitCode::from(1)
+                }
+            }
+        }
+
         _ => {
-            println!("Invalid input type. Use --file or --string.");
-    ExitCode::from(1)
-}
+            println!("Invalid input type. Use --file, --string, or --check.");
+            ExitCode::from(1)
+        }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_check_line_reads_the_tagged_form() {
+        assert_eq!(
+            parse_check_line("CRC64NVME (a.bin) = ae8b14860a799888"),
+            Some((Some("CRC64NVME"), "a.bin", 0xae8b14860a799888))
+        );
+    }
+
+    #[test]
+    fn parse_check_line_reads_the_coreutils_form() {
+        assert_eq!(
+            parse_check_line("ae8b14860a799888  a.bin"),
+            Some((None, "a.bin", 0xae8b14860a799888))
+        );
+    }
+
+    #[test]
+    fn parse_check_line_skips_blank_and_malformed_lines() {
+        assert_eq!(parse_check_line(""), None);
+        assert_eq!(parse_check_line("   "), None);
+        assert_eq!(parse_check_line("not a sumfile line"), None);
+        assert_eq!(parse_check_line("zzzz  a.bin"), None);
+    }
+
+    #[test]
+    fn format_checksum_covers_tag_hex_and_bare_decimal() {
+        assert_eq!(
+            format_checksum(0xae8b14860a799888, "a.bin", &algorithm::NVME, true, false),
+            "CRC64NVME (a.bin) = ae8b14860a799888"
+        );
+        assert_eq!(
+            format_checksum(0xae8b14860a799888, "a.bin", &algorithm::NVME, false, true),
+            "ae8b14860a799888"
+        );
+        assert_eq!(
+            format_checksum(12345, "a.bin", &algorithm::NVME, false, false),
+            "12345"
+        );
+    }
+
+    #[test]
+    fn split_args_and_flags_separates_dash_dash_from_positional() {
+        let args: Vec<String> = ["a.bin", "--tag", "-", "--algo=xz"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let (positional, flags) = split_args_and_flags(&args);
+
+        assert_eq!(positional, vec!["a.bin".to_string(), "-".to_string()]);
+        assert_eq!(flags, vec!["--tag".to_string(), "--algo=xz".to_string()]);
+    }
+
+    /// Writes `contents` to a fresh temp file and returns its path; the
+    /// caller is responsible for the file living long enough to be read.
+    fn write_temp_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "crc64fast_nvme_test_{}_{}_{name}",
+            std::process::id(),
+            name.len()
+        ));
+        fs::write(&path, contents).expect("failed to write temp file");
+        path
+    }
+
+    #[test]
+    fn run_check_reports_ok_for_a_matching_tagged_sumfile() {
+        let data_path = write_temp_file("run_check_ok_data", b"123456789");
+        let expected = calculate_crc_64_simd_from_string("123456789", &algorithm::NVME);
+        let sumfile_path = write_temp_file(
+            "run_check_ok_sumfile",
+            format!(
+                "CRC64NVME ({}) = {expected:016x}\n",
+                data_path.to_str().unwrap()
+            )
+            .as_bytes(),
+        );
+
+        let all_ok = run_check(sumfile_path.to_str().unwrap(), &algorithm::NVME).unwrap();
+
+        assert!(all_ok);
+        fs::remove_file(data_path).ok();
+        fs::remove_file(sumfile_path).ok();
+    }
+
+    #[test]
+    fn run_check_fails_on_an_algorithm_tag_mismatch_without_recomputing() {
+        // The listed value is the file's correct NVME checksum, so recomputing
+        // under NVME would report OK; only skipping on the tag makes it fail.
+        let data_path = write_temp_file("run_check_mismatch_data", b"123456789");
+        let sumfile_path = write_temp_file(
+            "run_check_mismatch_sumfile",
+            format!(
+                "CRC64XZ ({}) = ae8b14860a799888\n",
+                data_path.to_str().unwrap()
+            )
+            .as_bytes(),
+        );
+
+        let all_ok = run_check(sumfile_path.to_str().unwrap(), &algorithm::NVME).unwrap();
+
+        assert!(!all_ok);
+        fs::remove_file(data_path).ok();
+        fs::remove_file(sumfile_path).ok();
+    }
+}
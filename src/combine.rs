@@ -0,0 +1,197 @@
+//! Merging independently computed CRCs via the GF(2) matrix-operator method.
+//!
+//! A reflected CRC register is a vector in GF(2)^64, and "shift in one zero
+//! bit" is a linear operator on that vector. Composing the operator with
+//! itself `n` times (via repeated squaring) yields the operator for shifting
+//! in `n` zero bits, which lets us advance a CRC register past a block of
+//! data without touching the block's bytes at all, so long as we know the
+//! block's length. This is the same technique zlib's `crc32_combine` uses.
+
+use crate::algorithm::{self, Algorithm};
+
+const DIM: usize = 64;
+
+/// Applies the linear operator `mat` to the vector `vec`, i.e. computes
+/// `mat * vec` over GF(2): XOR together the rows of `mat` selected by the
+/// set bits of `vec`.
+fn gf2_matrix_times(mat: &[u64; DIM], mut vec: u64) -> u64 {
+    let mut sum = 0u64;
+    let mut i = 0;
+    while vec != 0 {
+        if vec & 1 != 0 {
+            sum ^= mat[i];
+        }
+        vec >>= 1;
+        i += 1;
+    }
+    sum
+}
+
+/// Computes `mat * mat`, i.e. the operator for applying `mat` twice.
+fn gf2_matrix_square(mat: &[u64; DIM]) -> [u64; DIM] {
+    let mut square = [0u64; DIM];
+    let mut n = 0;
+    while n < DIM {
+        square[n] = gf2_matrix_times(mat, mat[n]);
+        n += 1;
+    }
+    square
+}
+
+/// Advances both `a` and `init` by `len_b` zero *bytes* under the shared
+/// zero-bit operator, then folds in `b`. All three inputs and the result are
+/// raw CRC register values (the `init`/`xorout` bookkeeping has already been
+/// stripped by the caller).
+///
+/// `init` has to be shifted alongside `a` because `b` was itself computed
+/// starting from `init` rather than from zero: subtracting `init`'s shifted
+/// contribution out of the combined value is what "strips" `init` from `b`.
+pub(crate) fn combine_raw(a: u64, init: u64, b: u64, len_b: u64, poly_reflected: u64) -> u64 {
+    if len_b == 0 {
+        return a;
+    }
+
+    // Operator for one zero bit.
+    let mut odd = [0u64; DIM];
+    odd[0] = poly_reflected;
+    let mut row = 1u64;
+    for slot in odd.iter_mut().skip(1) {
+        *slot = row;
+        row <<= 1;
+    }
+    // Operator for two, then four, zero bits.
+    let mut even = gf2_matrix_square(&odd);
+    odd = gf2_matrix_square(&even);
+
+    let mut a = a;
+    let mut init = init;
+    let mut len = len_b;
+
+    loop {
+        // First time through, squaring the four-zero-bit operator yields the
+        // operator for one zero byte (eight zero bits).
+        even = gf2_matrix_square(&odd);
+        if len & 1 != 0 {
+            a = gf2_matrix_times(&even, a);
+            init = gf2_matrix_times(&even, init);
+        }
+        len >>= 1;
+        if len == 0 {
+            break;
+        }
+
+        odd = gf2_matrix_square(&even);
+        if len & 1 != 0 {
+            a = gf2_matrix_times(&odd, a);
+            init = gf2_matrix_times(&odd, init);
+        }
+        len >>= 1;
+        if len == 0 {
+            break;
+        }
+    }
+
+    a ^ init ^ b
+}
+
+/// Fuses two independently computed CRC-64/NVME values into the CRC of the
+/// concatenation of their inputs, without rescanning either buffer.
+///
+/// `crc_a` and `crc_b` are the finalized CRCs of adjacent byte ranges A and
+/// B, and `len_b` is the length of B in bytes. Returns the CRC of `A || B`.
+///
+/// Returns `crc_a` unchanged when `len_b == 0`.
+pub fn combine_crc64(crc_a: u64, crc_b: u64, len_b: u64) -> u64 {
+    combine_with_algorithm(&algorithm::NVME, crc_a, crc_b, len_b)
+}
+
+/// Like [`combine_crc64`], but for an arbitrary [`Algorithm`] instead of
+/// always assuming CRC-64/NVME.
+pub fn combine_with_algorithm(algorithm: &Algorithm, crc_a: u64, crc_b: u64, len_b: u64) -> u64 {
+    if len_b == 0 {
+        return crc_a;
+    }
+
+    let raw = combine_raw(
+        crc_a ^ algorithm.xorout,
+        algorithm.init,
+        crc_b ^ algorithm.xorout,
+        len_b,
+        algorithm.poly_reflected,
+    );
+    raw ^ algorithm.xorout
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_lcg::Lcg;
+    use crate::Digest;
+
+    fn crc64(data: &[u8]) -> u64 {
+        let mut d = Digest::new();
+        d.write(data);
+        d.sum64()
+    }
+
+    #[test]
+    fn combine_matches_single_pass() {
+        let mut rng = Lcg(0x2545F4914F6CDD1D);
+
+        for _ in 0..200 {
+            let len = rng.next_range(2000);
+            let data: Vec<u8> = (0..len).map(|_| rng.next_byte()).collect();
+            let split = rng.next_range(len + 1);
+            let (a, b) = data.split_at(split);
+
+            let whole = crc64(&data);
+            let combined = combine_crc64(crc64(a), crc64(b), b.len() as u64);
+
+            assert_eq!(
+                combined, whole,
+                "len={len} split={split} failed to combine"
+            );
+        }
+    }
+
+    #[test]
+    fn combine_with_empty_b_is_identity() {
+        let crc_a = crc64(b"some bytes");
+        assert_eq!(combine_crc64(crc_a, crc64(b""), 0), crc_a);
+    }
+
+    #[test]
+    fn digest_combine_matches_free_function() {
+        let mut a = Digest::new();
+        a.write(b"hello, ");
+        let mut b = Digest::new();
+        b.write(b"world!");
+
+        let combined = a.combine(&b, 6);
+
+        let mut whole = Digest::new();
+        whole.write(b"hello, world!");
+
+        assert_eq!(combined.sum64(), whole.sum64());
+        assert_eq!(combined.sum64(), combine_crc64(a.sum64(), b.sum64(), 6));
+    }
+
+    #[test]
+    fn combine_with_algorithm_matches_single_pass_for_xz() {
+        let mut a = Digest::new_with_algorithm(&algorithm::XZ);
+        a.write(b"hello, ");
+        let mut b = Digest::new_with_algorithm(&algorithm::XZ);
+        b.write(b"world!");
+
+        let combined = a.combine(&b, 6);
+
+        let mut whole = Digest::new_with_algorithm(&algorithm::XZ);
+        whole.write(b"hello, world!");
+
+        assert_eq!(combined.sum64(), whole.sum64());
+        assert_eq!(
+            combined.sum64(),
+            combine_with_algorithm(&algorithm::XZ, a.sum64(), b.sum64(), 6)
+        );
+    }
+}
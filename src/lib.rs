@@ -0,0 +1,141 @@
+//! Fast checksums for reflected CRC-64 variants, including CRC-64/NVME.
+//!
+//! ```
+//! use crc64fast_nvme::Digest;
+//!
+//! let mut c = Digest::new();
+//! c.write(b"123456789");
+//! assert_eq!(c.sum64(), 0xae8b14860a799888);
+//! ```
+//!
+//! [`Digest::new`] computes CRC-64/NVME. To use one of the other presets in
+//! [`algorithm`], construct with [`Digest::new_with_algorithm`]:
+//!
+//! ```
+//! use crc64fast_nvme::{algorithm, Digest};
+//!
+//! let mut c = Digest::new_with_algorithm(&algorithm::XZ);
+//! c.write(b"123456789");
+//! assert_eq!(c.sum64(), 0x995dc9bbdf1939fa);
+//! ```
+//!
+//! # Performance
+//!
+//! Despite the crate name, [`Digest::write`] is a portable byte-at-a-time,
+//! 256-entry table lookup — there is no SIMD folding or carryless
+//! multiplication here, unlike the upstream `crc64fast-nvme` crate this
+//! library's API is modeled after. That's a deliberate, accepted trade-off:
+//! a scalar core is what made [`Digest::combine`] (and, on top of it, the
+//! parallel/mmap path in [`Digest::from_mmap_parallel`]) straightforward to
+//! implement and keep correct across every [`Algorithm`] preset, at the cost
+//! of single-segment throughput. If you need the fastest possible single
+//! stream, reach for the real `crc64fast-nvme` crate directly and layer
+//! [`combine_crc64`] on top of it; restoring a SIMD-accelerated core here
+//! (e.g. by wrapping that crate's implementation) is open follow-up work,
+//! not something this crate currently does.
+
+pub mod algorithm;
+mod combine;
+mod parallel;
+mod table;
+#[cfg(test)]
+mod test_lcg;
+
+pub use algorithm::Algorithm;
+pub use combine::{combine_crc64, combine_with_algorithm};
+
+const NVME_TABLE: [u64; 256] = table::build(algorithm::NVME.poly_reflected);
+
+/// Represents an in-progress CRC-64 computation for some [`Algorithm`].
+#[derive(Clone)]
+pub struct Digest {
+    algorithm: &'static Algorithm,
+    table: [u64; 256],
+    state: u64,
+}
+
+impl Default for Digest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Digest {
+    /// Creates a new `Digest` computing CRC-64/NVME, with no data written to
+    /// it yet.
+    pub fn new() -> Self {
+        Digest {
+            algorithm: &algorithm::NVME,
+            table: NVME_TABLE,
+            state: algorithm::NVME.init,
+        }
+    }
+
+    /// Creates a new `Digest` for the given [`Algorithm`], with no data
+    /// written to it yet.
+    pub fn new_with_algorithm(algorithm: &'static Algorithm) -> Self {
+        Digest {
+            algorithm,
+            table: table::build(algorithm.poly_reflected),
+            state: algorithm.init,
+        }
+    }
+
+    /// Feeds `bytes` into the checksum.
+    pub fn write(&mut self, bytes: &[u8]) {
+        let mut state = self.state;
+        for &byte in bytes {
+            state = self.table[((state ^ byte as u64) & 0xFF) as usize] ^ (state >> 8);
+        }
+        self.state = state;
+    }
+
+    /// Returns the checksum of all data written so far.
+    pub fn sum64(&self) -> u64 {
+        self.state ^ self.algorithm.xorout
+    }
+
+    /// Merges `self` (the CRC of some byte range A) with `other` (the CRC of
+    /// an adjacent byte range B that is `other_len` bytes long), returning
+    /// the `Digest` of `A || B` without rescanning either range.
+    ///
+    /// The returned `Digest` can keep being written to as if it had
+    /// processed `A || B` itself.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug builds if `self` and `other` were created with
+    /// different algorithms.
+    pub fn combine(&self, other: &Digest, other_len: u64) -> Digest {
+        debug_assert_eq!(
+            self.algorithm, other.algorithm,
+            "cannot combine Digests computed with different Algorithms"
+        );
+
+        Digest {
+            algorithm: self.algorithm,
+            table: self.table,
+            state: combine::combine_raw(
+                self.state,
+                self.algorithm.init,
+                other.state,
+                other_len,
+                self.algorithm.poly_reflected,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_preset_matches_its_check_value() {
+        for algo in [&algorithm::NVME, &algorithm::XZ, &algorithm::ISO] {
+            let mut digest = Digest::new_with_algorithm(algo);
+            digest.write(b"123456789");
+            assert_eq!(digest.sum64(), algo.check, "{algo:?}");
+        }
+    }
+}
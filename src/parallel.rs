@@ -0,0 +1,146 @@
+//! Multi-threaded checksum computation over a memory-mapped file.
+//!
+//! Mirrors BLAKE3's mmap-and-recurse approach: the input is split into two
+//! halves (down to a minimum segment size), each half is checksummed on the
+//! Rayon thread pool, and the two partial results are stitched back together
+//! with [`Digest::combine`] rather than concatenating and rescanning.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use crate::{Algorithm, Digest};
+
+/// Below this size, a segment is checksummed directly rather than split
+/// further; splitting tiny segments just adds thread-pool overhead.
+const MIN_SEGMENT_LEN: usize = 1024 * 1024; // 1 MiB
+
+impl Digest {
+    /// Computes the checksum of `data` under `algorithm`, splitting it into
+    /// at most `max_segments` contiguous segments of `data.len() / max_segments`
+    /// bytes, rounded up (but never smaller than [`MIN_SEGMENT_LEN`]; the final
+    /// segment holds the remainder and may be shorter), and checksumming them
+    /// concurrently on the Rayon thread pool.
+    ///
+    /// Runs single-threaded when `data` is too small to be worth splitting.
+    pub fn from_slice_parallel(
+        data: &[u8],
+        max_segments: usize,
+        algorithm: &'static Algorithm,
+    ) -> Digest {
+        let max_segments = max_segments.max(1);
+        let segment_len = data.len().div_ceil(max_segments).max(MIN_SEGMENT_LEN);
+
+        if max_segments == 1 || data.len() <= segment_len {
+            let mut digest = Digest::new_with_algorithm(algorithm);
+            digest.write(data);
+            return digest;
+        }
+
+        checksum_recursive(data, segment_len, algorithm)
+    }
+
+    /// Memory-maps the file at `path` and computes its checksum under
+    /// `algorithm` using up to `max_segments` parallel segments (see
+    /// [`Digest::from_slice_parallel`]).
+    ///
+    /// Falls back to a buffered, single-threaded read over the file when it
+    /// can't be memory-mapped — e.g. it's empty, a pipe/FIFO, or `mmap`
+    /// otherwise fails.
+    pub fn from_mmap_parallel(
+        path: impl AsRef<Path>,
+        max_segments: usize,
+        algorithm: &'static Algorithm,
+    ) -> io::Result<Digest> {
+        let file = File::open(path)?;
+        let metadata = file.metadata()?;
+
+        // Only a genuine zero-length regular file is empty: pipes/FIFOs also
+        // report a length of 0 no matter how much data is written to them,
+        // so they still need to go through the buffered-read fallback below.
+        if metadata.is_file() && metadata.len() == 0 {
+            return Ok(Digest::new_with_algorithm(algorithm));
+        }
+
+        // SAFETY: `memmap2::Mmap::map` is unsound if `file` is truncated or
+        // its contents are mutated for the lifetime of the mapping, since
+        // that can surface as a SIGBUS or as the checksum reading torn data.
+        // We accept that risk here: this is a checksum tool operating on the
+        // caller's own files, which we have no way to lock against
+        // concurrent modification by the same caller or another process.
+        match unsafe { memmap2::Mmap::map(&file) } {
+            Ok(mmap) => Ok(Digest::from_slice_parallel(&mmap, max_segments, algorithm)),
+            Err(_) => from_buffered_reader(file, algorithm),
+        }
+    }
+}
+
+fn checksum_recursive(data: &[u8], segment_len: usize, algorithm: &'static Algorithm) -> Digest {
+    if data.len() <= segment_len {
+        let mut digest = Digest::new_with_algorithm(algorithm);
+        digest.write(data);
+        return digest;
+    }
+
+    // Split on a segment boundary so every leaf except the last is exactly
+    // `segment_len` long rather than drifting as the recursion narrows.
+    let mid = ((data.len() / 2) / segment_len).max(1) * segment_len;
+    let (left, right) = data.split_at(mid);
+
+    let (left_digest, right_digest) = rayon::join(
+        || checksum_recursive(left, segment_len, algorithm),
+        || checksum_recursive(right, segment_len, algorithm),
+    );
+
+    left_digest.combine(&right_digest, right.len() as u64)
+}
+
+fn from_buffered_reader(file: File, algorithm: &'static Algorithm) -> io::Result<Digest> {
+    const CHUNK_SIZE: usize = 100 * 1024 * 1024;
+
+    let mut digest = Digest::new_with_algorithm(algorithm);
+    let mut reader = BufReader::new(file);
+    let mut buffer = vec![0; CHUNK_SIZE];
+
+    loop {
+        let bytes_read = reader.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        digest.write(&buffer[..bytes_read]);
+    }
+
+    Ok(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm;
+    use crate::test_lcg::Lcg;
+
+    /// Exercises the actual multi-segment split-and-combine path in
+    /// `checksum_recursive`: the buffer is several times [`MIN_SEGMENT_LEN`],
+    /// so `from_slice_parallel` has to recurse and fold segments back
+    /// together with `Digest::combine` rather than taking the single-segment
+    /// shortcut.
+    #[test]
+    fn from_slice_parallel_matches_single_pass_across_segment_boundaries() {
+        let mut rng = Lcg(0x2545F4914F6CDD1D);
+        let data: Vec<u8> = (0..MIN_SEGMENT_LEN * 7 + 12345)
+            .map(|_| rng.next_byte())
+            .collect();
+
+        let mut whole = Digest::new_with_algorithm(&algorithm::NVME);
+        whole.write(&data);
+
+        for max_segments in [2, 4, 8] {
+            let parallel = Digest::from_slice_parallel(&data, max_segments, &algorithm::NVME);
+            assert_eq!(
+                parallel.sum64(),
+                whole.sum64(),
+                "max_segments={max_segments} didn't match single-pass"
+            );
+        }
+    }
+}
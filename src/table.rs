@@ -0,0 +1,23 @@
+//! Byte-wise lookup table for the table-driven CRC update.
+
+/// Builds the standard 256-entry reflected CRC table for a given
+/// bit-reversed ("reflected") polynomial.
+pub(crate) const fn build(poly_reflected: u64) -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut byte = 0usize;
+    while byte < 256 {
+        let mut crc = byte as u64;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ poly_reflected
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[byte] = crc;
+        byte += 1;
+    }
+    table
+}
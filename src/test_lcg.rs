@@ -0,0 +1,26 @@
+//! A small deterministic PRNG shared by this crate's tests, so they don't
+//! need an external `rand` dependency.
+
+#[cfg(test)]
+pub(crate) struct Lcg(pub(crate) u64);
+
+#[cfg(test)]
+impl Lcg {
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        // Knuth's MMIX multiplier with an increment of 1 (as in musl's rand).
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1);
+        self.0
+    }
+
+    pub(crate) fn next_byte(&mut self) -> u8 {
+        (self.next_u64() >> 56) as u8
+    }
+
+    pub(crate) fn next_range(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            0
+        } else {
+            (self.next_u64() % bound as u64) as usize
+        }
+    }
+}